@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::errors::CargoPlayError;
+
+/// Long and short forms of every `Options` flag that takes a value, used by
+/// `Config::expand_alias` to know whether dropping a re-specified flag also
+/// means dropping the token after it.
+const VALUE_FLAGS: &[&str] = &[
+    "--mode",
+    "-m",
+    "--edition",
+    "-e",
+    "--toolchain",
+    "-t",
+    "--cargo-option",
+    "--save",
+    "--message-format",
+    "--config",
+];
+
+/// Per-user configuration read from `~/.config/cargo-play/config.toml`,
+/// borrowing Cargo's own idea of alias expansion plus a table of
+/// dependencies that should be merged into every script by default.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub alias: HashMap<String, String>,
+
+    #[serde(rename = "default-dependencies", default)]
+    pub default_dependencies: HashMap<String, toml::Value>,
+}
+
+impl Config {
+    /// Load the config from `path`, falling back to the default
+    /// `~/.config/cargo-play/config.toml` location when `path` is `None`.
+    /// Missing files are not an error; they just yield an empty `Config`.
+    pub fn load(path: Option<&Path>) -> Result<Self, CargoPlayError> {
+        let path = match path.map(Path::to_path_buf).or_else(default_config_path) {
+            Some(p) if p.is_file() => p,
+            _ => return Ok(Self::default()),
+        };
+
+        let raw = fs::read_to_string(&path)
+            .map_err(|e| CargoPlayError::ConfigError(path.clone(), e.to_string()))?;
+
+        toml::from_str(&raw).map_err(|e| CargoPlayError::ConfigError(path, e.to_string()))
+    }
+
+    /// Expand a leading alias token (e.g. `bench`) in `args` into its
+    /// configured argument vector, much like `Options::parse` already pulls
+    /// the `+toolchain` pseudo-flag out of the stream.
+    ///
+    /// structopt aborts with `UnexpectedMultipleUsage` if the same flag
+    /// shows up twice in the argument stream, so a flag the caller passed
+    /// explicitly after the alias must replace the alias's copy rather than
+    /// sit alongside it: any alias-provided flag (and its value, for the
+    /// flags in `VALUE_FLAGS`) that also appears in the caller's explicit
+    /// arguments is dropped from the expansion, leaving the explicit one as
+    /// the only occurrence. Flags are matched by their exact spelling, so an
+    /// alias's `--mode` is not dropped for an explicit `-m` (or `--mode=...`).
+    pub fn expand_alias(&self, args: Vec<String>) -> Vec<String> {
+        let alias = match args.get(1) {
+            Some(a) => a.clone(),
+            None => return args,
+        };
+
+        let expansion = match self.alias.get(&alias) {
+            Some(e) => e,
+            None => return args,
+        };
+
+        let program = args[0].clone();
+        let explicit: Vec<String> = args.into_iter().skip(2).collect();
+        let explicit_flags: std::collections::HashSet<&str> = explicit
+            .iter()
+            .filter(|a| a.starts_with('-'))
+            .map(String::as_str)
+            .collect();
+
+        let expansion_tokens: Vec<String> = expansion.split_whitespace().map(String::from).collect();
+        let mut expanded = vec![program];
+
+        let mut tokens = expansion_tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            if token.starts_with('-') && explicit_flags.contains(token.as_str()) {
+                if VALUE_FLAGS.contains(&token.as_str()) {
+                    tokens.next();
+                }
+                continue;
+            }
+            expanded.push(token);
+        }
+
+        expanded.extend(explicit);
+        expanded
+    }
+
+    /// Merge the user's default dependency table into an inferred/inline
+    /// manifest's `[dependencies]` table, without overriding anything the
+    /// script already declares.
+    pub fn merge_default_dependencies(&self, deps: &mut HashMap<String, toml::Value>) {
+        for (name, spec) in &self.default_dependencies {
+            deps.entry(name.clone()).or_insert_with(|| spec.clone());
+        }
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("cargo-play").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn config_with_alias(name: &str, expansion: &str) -> Config {
+        let mut alias = HashMap::new();
+        alias.insert(name.to_string(), expansion.to_string());
+        Config {
+            alias,
+            default_dependencies: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn expands_known_alias() {
+        let config = config_with_alias("bench", "--mode bench --release");
+        let args = vec![
+            "cargo-play".to_string(),
+            "bench".to_string(),
+            "foo.rs".to_string(),
+        ];
+
+        assert_eq!(
+            config.expand_alias(args),
+            vec!["cargo-play", "--mode", "bench", "--release", "foo.rs"]
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_alias_untouched() {
+        let config = config_with_alias("bench", "--mode bench --release");
+        let args = vec!["cargo-play".to_string(), "foo.rs".to_string()];
+
+        assert_eq!(config.expand_alias(args.clone()), args);
+    }
+
+    #[test]
+    fn explicit_flag_after_alias_drops_the_alias_copy() {
+        let config = config_with_alias("bench", "--mode bench --release");
+        let args = vec![
+            "cargo-play".to_string(),
+            "bench".to_string(),
+            "--mode".to_string(),
+            "test".to_string(),
+            "foo.rs".to_string(),
+        ];
+
+        // `--mode` is re-specified explicitly, so the alias's `--mode bench`
+        // (flag and value) is dropped, leaving only one `--mode` in the
+        // stream for structopt to parse
+        assert_eq!(
+            config.expand_alias(args),
+            vec!["cargo-play", "--release", "--mode", "test", "foo.rs"]
+        );
+    }
+
+    #[test]
+    fn explicit_flag_after_alias_wins_the_full_parse() {
+        use crate::options::Options;
+        use structopt::StructOpt;
+
+        let dir = std::env::temp_dir().join(format!(
+            "cargo-play-config-test-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let main = dir.join("main.rs");
+        fs::write(&main, "fn main() {}").unwrap();
+
+        let config = config_with_alias("bench", "--mode bench --release");
+        let args = vec![
+            "cargo-play".to_string(),
+            "bench".to_string(),
+            "--mode".to_string(),
+            "test".to_string(),
+            main.to_string_lossy().into_owned(),
+        ];
+
+        let expanded = config.expand_alias(args);
+        let options = Options::from_iter_safe(expanded).unwrap();
+
+        assert_eq!(options.mode.as_deref(), Some("test"));
+        assert!(options.release);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
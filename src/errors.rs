@@ -0,0 +1,41 @@
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum CargoPlayError {
+    InvalidEdition(String),
+    InvalidMessageFormat(String),
+    ConfigError(PathBuf, String),
+    RegistryError(String, String),
+    NoVersionFound(String),
+    OfflineCacheMiss(String),
+    ManifestParseError(usize, String),
+}
+
+impl fmt::Display for CargoPlayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CargoPlayError::InvalidEdition(s) => write!(f, "invalid edition: {}", s),
+            CargoPlayError::InvalidMessageFormat(s) => write!(f, "invalid message format: {}", s),
+            CargoPlayError::ConfigError(path, reason) => {
+                write!(f, "failed to read config {:?}: {}", path, reason)
+            }
+            CargoPlayError::RegistryError(name, reason) => {
+                write!(f, "failed to resolve version for `{}`: {}", name, reason)
+            }
+            CargoPlayError::NoVersionFound(name) => {
+                write!(f, "no published version found for `{}`", name)
+            }
+            CargoPlayError::OfflineCacheMiss(name) => write!(
+                f,
+                "`{}` is not cached and --infer-offline disallows network access",
+                name
+            ),
+            CargoPlayError::ManifestParseError(line_no, reason) => {
+                write!(f, "invalid manifest header at line {}: {}", line_no, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CargoPlayError {}
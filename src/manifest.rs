@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::Path;
+
+use crate::errors::CargoPlayError;
+
+const HEADER_PREFIX: &str = "//#";
+
+struct HeaderLine {
+    line_no: usize,
+    content: String,
+}
+
+/// Parses the `//#` header lines at the top of a script into a
+/// `[dependencies]` table, so a script can declare anything Cargo itself
+/// understands in a `Cargo.toml` dependency entry: version strings,
+/// `git`/`branch`/`rev`, `path`, `features`, `default-features = false`,
+/// `optional`, and so on.
+#[derive(Debug, Default)]
+pub struct Manifest {
+    pub dependencies: toml::value::Table,
+}
+
+impl Manifest {
+    /// Parse every `//#` header line at the top of `src`. Scanning stops at
+    /// the first line that is neither blank nor a header, mirroring how the
+    /// rest of the file is otherwise ignored.
+    ///
+    /// The header lines are accumulated into a single TOML document (with
+    /// non-header lines kept blank so line numbers line up with `src`)
+    /// before being parsed as one unit, so a dependency entry can span
+    /// multiple `//#` lines.
+    pub fn parse(src: &str) -> Result<Self, CargoPlayError> {
+        let headers = Self::header_lines(src);
+        let last_line_no = headers.iter().map(|h| h.line_no).max().unwrap_or(0);
+
+        let mut by_line = std::collections::HashMap::new();
+        for header in &headers {
+            by_line.insert(header.line_no, header.content.as_str());
+        }
+
+        let mut doc = String::new();
+        for line_no in 1..=last_line_no {
+            doc.push_str(by_line.get(&line_no).copied().unwrap_or(""));
+            doc.push('\n');
+        }
+
+        let dependencies: toml::value::Table = toml::from_str(&doc).map_err(|e| {
+            let line_no = e.line_col().map(|(row, _)| row + 1).unwrap_or(0);
+            CargoPlayError::ManifestParseError(line_no, e.to_string())
+        })?;
+
+        Ok(Self { dependencies })
+    }
+
+    /// Parse the headers out of a file on disk
+    pub fn parse_file(path: &Path) -> Result<Self, CargoPlayError> {
+        let src = fs::read_to_string(path)
+            .map_err(|e| CargoPlayError::ManifestParseError(0, e.to_string()))?;
+        Self::parse(&src)
+    }
+
+    /// Render this manifest's `[dependencies]` section the way it would
+    /// appear in a generated `Cargo.toml`, for `--print-manifest`.
+    ///
+    /// Goes through `toml::to_string` rather than formatting each value by
+    /// hand, so table-valued specs (`git`, `path`, `features`, ...) come out
+    /// as valid, re-parseable TOML instead of their `Display` debug form.
+    pub fn to_dependencies_section(&self) -> Result<String, CargoPlayError> {
+        let mut doc = toml::value::Table::new();
+        doc.insert(
+            "dependencies".to_string(),
+            toml::Value::Table(self.dependencies.clone()),
+        );
+
+        toml::to_string(&doc).map_err(|e| CargoPlayError::ManifestParseError(0, e.to_string()))
+    }
+
+    fn header_lines(src: &str) -> Vec<HeaderLine> {
+        src.lines()
+            .enumerate()
+            .take_while(|(_, l)| l.trim().is_empty() || l.trim_start().starts_with(HEADER_PREFIX))
+            .filter_map(|(i, l)| {
+                l.trim_start().strip_prefix(HEADER_PREFIX).map(|rest| HeaderLine {
+                    line_no: i + 1,
+                    content: rest.trim().to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_version_and_git_headers() {
+        let src = "//# rand = \"0.8.5\"\n//# dtoa = { git = \"https://github.com/dtolnay/dtoa.git\" }\n\nfn main() {}\n";
+        let manifest = Manifest::parse(src).unwrap();
+
+        assert_eq!(
+            manifest.dependencies.get("rand").unwrap().as_str(),
+            Some("0.8.5")
+        );
+        assert!(manifest.dependencies.get("dtoa").unwrap().is_table());
+    }
+
+    #[test]
+    fn parses_full_dependency_table() {
+        let src = "//# serde = { version = \"1\", features = [\"derive\"], default-features = false, optional = true }\n";
+        let manifest = Manifest::parse(src).unwrap();
+        let serde = manifest.dependencies.get("serde").unwrap().as_table().unwrap();
+
+        assert_eq!(serde.get("version").unwrap().as_str(), Some("1"));
+        assert_eq!(serde.get("default-features").unwrap().as_bool(), Some(false));
+        assert_eq!(serde.get("optional").unwrap().as_bool(), Some(true));
+    }
+
+    #[test]
+    fn stops_at_first_non_header_line() {
+        let src = "//# rand = \"0.8.5\"\nfn main() {}\n//# ignored = \"1.0\"\n";
+        let manifest = Manifest::parse(src).unwrap();
+
+        assert!(manifest.dependencies.contains_key("rand"));
+        assert!(!manifest.dependencies.contains_key("ignored"));
+    }
+
+    #[test]
+    fn reports_offending_line_number() {
+        let src = "//# rand = \"0.8.5\"\n//# not valid toml\n";
+        let err = Manifest::parse(src).unwrap_err();
+
+        match err {
+            CargoPlayError::ManifestParseError(line_no, _) => assert_eq!(line_no, 2),
+            other => panic!("expected ManifestParseError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn renders_table_valued_dep_as_reparseable_toml() {
+        let src = "//# dtoa = { git = \"https://github.com/dtolnay/dtoa.git\", branch = \"master\" }\n";
+        let manifest = Manifest::parse(src).unwrap();
+
+        let section = manifest.to_dependencies_section().unwrap();
+        let reparsed: toml::Value = toml::from_str(&section).unwrap();
+        let dtoa = reparsed["dependencies"]["dtoa"].as_table().unwrap();
+        assert_eq!(
+            dtoa.get("git").unwrap().as_str(),
+            Some("https://github.com/dtolnay/dtoa.git")
+        );
+        assert_eq!(dtoa.get("branch").unwrap().as_str(), Some("master"));
+    }
+}
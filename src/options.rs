@@ -6,7 +6,11 @@ use std::vec::Vec;
 use sha1::{Sha1, Digest};
 use structopt::StructOpt;
 
+use crate::config::Config;
 use crate::errors::CargoPlayError;
+use crate::manifest::Manifest;
+use crate::registry::Resolver;
+use crate::watch::Watcher;
 
 #[derive(Debug, Clone)]
 pub enum RustEdition {
@@ -47,6 +51,39 @@ impl Default for RustEdition {
     }
 }
 
+#[derive(Debug, Clone)]
+pub enum MessageFormat {
+    Json,
+    Short,
+    Human,
+}
+
+impl FromStr for MessageFormat {
+    type Err = CargoPlayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "json" {
+            Ok(MessageFormat::Json)
+        } else if s == "short" {
+            Ok(MessageFormat::Short)
+        } else if s == "human" {
+            Ok(MessageFormat::Human)
+        } else {
+            Err(CargoPlayError::InvalidMessageFormat(s.into()))
+        }
+    }
+}
+
+impl Into<String> for MessageFormat {
+    fn into(self) -> String {
+        match self {
+            MessageFormat::Json => "json".into(),
+            MessageFormat::Short => "short".into(),
+            MessageFormat::Human => "human".into(),
+        }
+    }
+}
+
 #[derive(Debug, StructOpt, Default)]
 #[structopt(
     name = "cargo-play",
@@ -132,6 +169,34 @@ pub struct Options {
     #[structopt(long = "infer", short = "i")]
     pub infer: bool,
 
+    /// [experimental] Resolve `--infer`red crate versions from the local
+    /// cache only, without touching the network
+    #[structopt(long = "infer-offline")]
+    pub infer_offline: bool,
+
+    /// Print the synthesized `[dependencies]` section and exit, without
+    /// building or running anything
+    #[structopt(long = "print-manifest")]
+    pub print_manifest: bool,
+
+    /// Keep running, re-running `mode` every time a source file (or a
+    /// `path = ...` dependency) changes on disk; a dependency directory is
+    /// checked by its own modification time only, not the files inside it
+    #[structopt(long = "watch", short = "w")]
+    pub watch: bool,
+
+    #[structopt(
+        long = "message-format",
+        possible_values = &["json", "short", "human"]
+    )]
+    /// Output compiler diagnostics in the given format, forwarded to Cargo
+    pub message_format: Option<MessageFormat>,
+
+    #[structopt(long = "config", parse(from_os_str))]
+    /// Override the default config file location
+    /// (`~/.config/cargo-play/config.toml`)
+    pub config: Option<PathBuf>,
+
     #[structopt(multiple = true, last = true)]
     /// Arguments passed to the underlying program
     pub args: Vec<String>,
@@ -168,6 +233,49 @@ impl Options {
         format!("cargo-play.{}", self.src_hash()).into()
     }
 
+    /// The `--message-format` flag to forward to Cargo, if any
+    pub fn cargo_message_format(&self) -> Option<String> {
+        self.message_format
+            .clone()
+            .map(|f| format!("--message-format={}", Into::<String>::into(f)))
+    }
+
+    /// Whether the run banner should be suppressed so stdout stays
+    /// machine-parseable (only relevant for `--message-format=json`)
+    pub fn quiet_banner(&self) -> bool {
+        matches!(self.message_format, Some(MessageFormat::Json))
+    }
+
+    /// The crates.io sparse index resolver used by `--infer`, caching
+    /// under a `cargo-play-registry-cache` dir in the system temp dir that
+    /// is shared by every run
+    pub fn registry(&self) -> Resolver {
+        Resolver::new(
+            std::env::temp_dir().join("cargo-play-registry-cache"),
+            self.infer_offline,
+        )
+    }
+
+    /// Parse and merge the inline `//#` manifest headers across every
+    /// source file, later files overriding earlier ones on conflict
+    pub fn manifest(&self) -> Result<Manifest, CargoPlayError> {
+        let mut merged = Manifest::default();
+
+        for path in &self.src {
+            let partial = Manifest::parse_file(path)?;
+            for (name, spec) in partial.dependencies {
+                merged.dependencies.insert(name, spec);
+            }
+        }
+
+        Ok(merged)
+    }
+
+    /// A `Watcher` covering every path relevant to this run, for `--watch`
+    pub fn watcher(&self) -> Result<Watcher, CargoPlayError> {
+        Watcher::for_options(self)
+    }
+
     fn with_toolchain(mut self, toolchain: Option<String>) -> Self {
         self.toolchain = toolchain;
         self
@@ -186,6 +294,11 @@ impl Options {
             args.next();
         }
 
+        let args: Vec<String> = args.collect();
+        let config_override = find_flag_value(&args, "--config").map(PathBuf::from);
+        let config = Config::load(config_override.as_deref()).unwrap_or_default();
+        let args = config.expand_alias(args).into_iter();
+
         let toolchain = args
             .clone()
             .find(|x| x.starts_with('+'))
@@ -195,6 +308,15 @@ impl Options {
     }
 }
 
+/// Find the value following a `--flag` in a raw argument stream, before
+/// structopt has had a chance to parse it
+fn find_flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|x| x == flag)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
 /// Convert `std::ffi::OsStr` to an absolute `std::path::PathBuf`
 fn osstr_to_abspath(v: &OsStr) -> Result<PathBuf, OsString> {
     if let Ok(r) = PathBuf::from(v).canonicalize() {
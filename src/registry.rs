@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::PathBuf;
+
+use semver::Version;
+use serde::Deserialize;
+
+use crate::errors::CargoPlayError;
+
+/// One line of a crates.io sparse index file: one JSON object per published
+/// version of a crate, oldest first.
+#[derive(Debug, Deserialize)]
+struct IndexEntry {
+    vers: String,
+    yanked: bool,
+}
+
+/// Resolves crate names inferred by `--infer` to a pinned version using the
+/// crates.io sparse HTTP index, caching each crate's index file under the
+/// temp dir so repeated runs are offline-friendly.
+pub struct Resolver {
+    cache_dir: PathBuf,
+    offline: bool,
+}
+
+impl Resolver {
+    pub fn new(cache_dir: PathBuf, offline: bool) -> Self {
+        Self { cache_dir, offline }
+    }
+
+    /// Resolve `name` to the highest non-yanked stable version, falling
+    /// back to the highest non-yanked prerelease only if every stable
+    /// version is yanked or none has been published.
+    pub fn resolve(&self, name: &str) -> Result<String, CargoPlayError> {
+        let raw = self.fetch_index(name)?;
+
+        let mut stable: Vec<Version> = Vec::new();
+        let mut prerelease: Vec<Version> = Vec::new();
+
+        for line in raw.lines().filter(|l| !l.trim().is_empty()) {
+            let entry: IndexEntry = serde_json::from_str(line)
+                .map_err(|e| CargoPlayError::RegistryError(name.into(), e.to_string()))?;
+
+            if entry.yanked {
+                continue;
+            }
+
+            if let Ok(version) = Version::parse(&entry.vers) {
+                if version.pre.is_empty() {
+                    stable.push(version);
+                } else {
+                    prerelease.push(version);
+                }
+            }
+        }
+
+        stable.sort();
+        prerelease.sort();
+
+        stable
+            .pop()
+            .or_else(|| prerelease.pop())
+            .map(|v| v.to_string())
+            .ok_or_else(|| CargoPlayError::NoVersionFound(name.into()))
+    }
+
+    fn fetch_index(&self, name: &str) -> Result<String, CargoPlayError> {
+        let cache_path = self.cache_dir.join(name);
+
+        if let Ok(cached) = fs::read_to_string(&cache_path) {
+            return Ok(cached);
+        }
+
+        if self.offline {
+            return Err(CargoPlayError::OfflineCacheMiss(name.into()));
+        }
+
+        let body = ureq::get(&index_url(name))
+            .call()
+            .map_err(|e| CargoPlayError::RegistryError(name.into(), e.to_string()))?
+            .into_string()
+            .map_err(|e| CargoPlayError::RegistryError(name.into(), e.to_string()))?;
+
+        fs::create_dir_all(&self.cache_dir)
+            .map_err(|e| CargoPlayError::RegistryError(name.into(), e.to_string()))?;
+        fs::write(&cache_path, &body)
+            .map_err(|e| CargoPlayError::RegistryError(name.into(), e.to_string()))?;
+
+        Ok(body)
+    }
+}
+
+/// Build the crates.io sparse index URL for a crate name, per the layout
+/// documented at <https://doc.rust-lang.org/cargo/reference/registries.html#index-format>:
+/// 1- and 2-character names live directly under `1/` and `2/`, 3-character
+/// names are split as `3/{first-char}`, and everything else is split into
+/// two two-character prefix directories.
+fn index_url(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let prefix = match lower.len() {
+        1 => "1".to_string(),
+        2 => "2".to_string(),
+        3 => format!("3/{}", &lower[0..1]),
+        _ => format!("{}/{}", &lower[0..2], &lower[2..4]),
+    };
+
+    format!("https://index.crates.io/{}/{}", prefix, lower)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_url_short_names() {
+        assert_eq!(index_url("a"), "https://index.crates.io/1/a");
+        assert_eq!(index_url("ab"), "https://index.crates.io/2/ab");
+        assert_eq!(index_url("abc"), "https://index.crates.io/3/a/abc");
+    }
+
+    #[test]
+    fn index_url_long_names() {
+        assert_eq!(index_url("serde"), "https://index.crates.io/se/rd/serde");
+    }
+
+    #[test]
+    fn resolve_picks_highest_non_yanked_stable() {
+        let dir = std::env::temp_dir().join(format!(
+            "cargo-play-registry-test-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("foo"),
+            concat!(
+                "{\"vers\":\"0.1.0\",\"yanked\":false}\n",
+                "{\"vers\":\"0.2.0\",\"yanked\":true}\n",
+                "{\"vers\":\"0.3.0-alpha\",\"yanked\":false}\n",
+                "{\"vers\":\"0.1.5\",\"yanked\":false}\n",
+            ),
+        )
+        .unwrap();
+
+        let resolver = Resolver::new(dir.clone(), true);
+        assert_eq!(resolver.resolve("foo").unwrap(), "0.1.5");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolve_falls_back_to_prerelease() {
+        let dir = std::env::temp_dir().join(format!(
+            "cargo-play-registry-test-prerelease-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("bar"),
+            "{\"vers\":\"1.0.0-beta.1\",\"yanked\":false}\n",
+        )
+        .unwrap();
+
+        let resolver = Resolver::new(dir.clone(), true);
+        assert_eq!(resolver.resolve("bar").unwrap(), "1.0.0-beta.1");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
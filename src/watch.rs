@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::errors::CargoPlayError;
+use crate::options::Options;
+
+/// Debounce window between detecting a change and triggering a rerun, so a
+/// burst of writes (an editor's save-then-reformat) only triggers one cycle
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Watches every path relevant to a run -- the script's own source files
+/// plus any `path = ...` dependencies from its inline manifest -- and lets
+/// `--watch` block until one of them changes.
+///
+/// Changes are detected by comparing each watched path's own modification
+/// time; a dependency directory is not walked, so editing a file inside it
+/// does not count as a change.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    mtimes: HashMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    pub fn for_options(options: &Options) -> Result<Self, CargoPlayError> {
+        let mut paths = options.src.clone();
+
+        for spec in options.manifest()?.dependencies.values() {
+            if let Some(path) = spec.as_table().and_then(|t| t.get("path")).and_then(|p| p.as_str()) {
+                paths.push(PathBuf::from(path));
+            }
+        }
+
+        let mtimes = Self::snapshot(&paths);
+        Ok(Self { paths, mtimes })
+    }
+
+    /// Block until at least one watched path changes since the last call
+    pub fn wait_for_change(&mut self) {
+        loop {
+            std::thread::sleep(DEBOUNCE);
+
+            let mut current = Self::snapshot(&self.paths);
+            if current != self.mtimes {
+                // keep waiting until a full window passes with no further
+                // change, so a burst of writes settles into one rerun
+                loop {
+                    std::thread::sleep(DEBOUNCE);
+                    let settled = Self::snapshot(&self.paths);
+                    if settled == current {
+                        break;
+                    }
+                    current = settled;
+                }
+                self.mtimes = current;
+                return;
+            }
+        }
+    }
+
+    fn snapshot(paths: &[PathBuf]) -> HashMap<PathBuf, SystemTime> {
+        paths
+            .iter()
+            .filter_map(|p| {
+                std::fs::metadata(p)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .map(|t| (p.clone(), t))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn watches_both_source_and_path_dependencies() {
+        let dir = std::env::temp_dir().join(format!("cargo-play-watch-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let main = dir.join("main.rs");
+        fs::write(&main, "//# local = { path = \"./local-crate\" }\nfn main() {}").unwrap();
+
+        let options = Options::with_files(vec![main.clone()]);
+        let watcher = Watcher::for_options(&options).unwrap();
+
+        assert!(watcher.paths.contains(&main));
+        assert!(watcher.paths.iter().any(|p| p == &PathBuf::from("./local-crate")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}